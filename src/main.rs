@@ -1,5 +1,7 @@
 use clap::Parser;
 use log::debug;
+use log::warn;
+use std::collections::BTreeMap;
 use std::collections::BTreeSet;
 use std::fmt;
 use std::io;
@@ -11,6 +13,16 @@ use std::sync::atomic::Ordering;
 struct Args {
     /// Path to the file that contains values of cells.
     path: std::path::PathBuf,
+
+    /// Count the puzzle's solutions (capped at two) instead of solving it,
+    /// warning when more than one exists.
+    #[clap(long, visible_alias = "unique")]
+    count: bool,
+
+    /// Log the ordered list of deductions and the technique tally alongside the
+    /// difficulty grade.
+    #[clap(long)]
+    explain: bool,
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -18,13 +30,35 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let args = Args::parse();
 
-    let board = solve(open(&args.path)?)?;
-    println!("{}", board);
+    let board = open(&args.path)?;
+
+    if args.count {
+        let solutions = solve_all(board, 2);
+        println!("{}", solutions.len());
+        match solutions.len() {
+            0 => warn!("puzzle has no solution"),
+            1 => {}
+            n => warn!("puzzle is not unique: found at least {n} solutions"),
+        }
+    } else {
+        let (board, report) = solve(board)?;
+        print!("{board}");
+
+        if args.explain {
+            for deduction in &report.log {
+                println!("{deduction}");
+            }
+            for (technique, count) in &report.counts {
+                println!("{technique} \u{d7} {count}");
+            }
+        }
+        println!("difficulty: {}", report.grade());
+    }
 
     Ok(())
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 enum Cell {
     NotSolved(BTreeSet<u8>),
     Solved(u8),
@@ -39,34 +73,77 @@ impl fmt::Display for Cell {
     }
 }
 
-#[derive(Debug, Clone)]
-struct Board(Vec<Cell>);
+/// Dimensions of a board, derived from the number of cells in the input.
+///
+/// A board is `side`×`side` cells and is partitioned into `box_size`×`box_size`
+/// boxes, where `side == box_size²`. The classic puzzle is `box_size == 3`
+/// (a 9×9 board of 81 cells); 4×4 (`box_size == 2`) and 16×16 (`box_size == 4`)
+/// fall out of the same relation.
+#[derive(Debug, Clone, Copy)]
+struct BoardSpec {
+    box_size: usize,
+    side: usize,
+    cells: usize,
+}
 
-impl From<Vec<Cell>> for Board {
-    fn from(v: Vec<Cell>) -> Self {
-        Self(v)
+impl BoardSpec {
+    /// Derives the spec from a cell count by taking √cells for the side and
+    /// √side for the box size, returning `None` unless both are exact.
+    fn from_cells(cells: usize) -> Option<Self> {
+        let side = (cells as f64).sqrt() as usize;
+        if side * side != cells {
+            return None;
+        }
+
+        let box_size = (side as f64).sqrt() as usize;
+        if box_size * box_size != side {
+            return None;
+        }
+
+        Some(Self {
+            box_size,
+            side,
+            cells,
+        })
     }
 }
 
+#[derive(Debug, Clone)]
+struct Board {
+    spec: BoardSpec,
+    cells: Vec<Cell>,
+}
+
 impl std::ops::Deref for Board {
     type Target = [Cell];
 
     fn deref(&self) -> &Self::Target {
-        self.0.as_slice()
+        self.cells.as_slice()
     }
 }
 
 impl std::ops::DerefMut for Board {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        self.0.as_mut_slice()
+        self.cells.as_mut_slice()
     }
 }
 
 impl fmt::Display for Board {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for r in 0..9 {
-            for c in 0..9 {
-                write!(f, "{}", self[r * 9 + c])?;
+        let side = self.spec.side;
+        // Boards larger than 9×9 need two-digit cells, so pad every cell to the
+        // width of `side` and separate columns; 9×9 and smaller keep the classic
+        // one-character-per-cell layout with no separator.
+        let width = side.to_string().len();
+        for r in 0..side {
+            for c in 0..side {
+                if c > 0 && width > 1 {
+                    write!(f, " ")?;
+                }
+                match &self[r * side + c] {
+                    Cell::NotSolved(_) => write!(f, "{:>width$}", "")?,
+                    Cell::Solved(n) => write!(f, "{n:>width$}")?,
+                }
             }
             writeln!(f)?;
         }
@@ -75,81 +152,246 @@ impl fmt::Display for Board {
     }
 }
 
-impl From<u8> for Cell {
-    fn from(b: u8) -> Self {
-        match b {
-            0 => Self::NotSolved((1..=9).collect()),
-            1..=9 => Self::Solved(b),
-            _ => panic!(),
-        }
+fn open(path: impl AsRef<std::path::Path>) -> io::Result<Board> {
+    let content = std::fs::read_to_string(path)?;
+
+    let values = parse_values(&content).ok_or(io::ErrorKind::InvalidData)?;
+    let spec = BoardSpec::from_cells(values.len()).ok_or(io::ErrorKind::InvalidData)?;
+
+    let side = spec.side as u8;
+    if values.iter().any(|&v| v > side) {
+        return Err(io::ErrorKind::InvalidData.into());
     }
+
+    let cells = values
+        .into_iter()
+        .map(|v| match v {
+            0 => Cell::NotSolved((1..=side).collect()),
+            n => Cell::Solved(n),
+        })
+        .collect();
+
+    Ok(Board { spec, cells })
 }
 
-fn open(path: impl AsRef<std::path::Path>) -> io::Result<Board> {
-    use io::Read;
+/// Parses the cell values out of the file contents.
+///
+/// Tokens separated by whitespace or commas are read first so that two-digit
+/// values (10‥25) can be given for boards larger than 9×9; a token of `0` or
+/// `.` is an empty cell. When the tokens do not make a valid board (as with the
+/// classic one-character-per-cell layout) the contents are read one digit at a
+/// time, skipping any other character, with `0` or `.` meaning empty.
+fn parse_values(content: &str) -> Option<Vec<u8>> {
+    let tokens = content
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|t| !t.is_empty())
+        .collect::<Vec<_>>();
 
-    let board = std::fs::File::open(path)?
-        .bytes()
-        .filter_map(Result::ok)
-        .filter_map(|b| match b {
-            0x30..=0x39 => Some(b - 0x30),
+    if tokens.len() > 1 {
+        let values = tokens
+            .iter()
+            .map(|t| match *t {
+                "." | "0" => Some(0),
+                t => t.parse::<u8>().ok(),
+            })
+            .collect::<Option<Vec<_>>>();
+        if let Some(values) = values
+            && BoardSpec::from_cells(values.len()).is_some()
+        {
+            return Some(values);
+        }
+    }
+
+    let values = content
+        .chars()
+        .filter_map(|c| match c {
+            '.' => Some(0),
+            '0'..='9' => Some(c as u8 - b'0'),
             _ => None,
         })
-        .map(Cell::from)
         .collect::<Vec<_>>();
+    BoardSpec::from_cells(values.len()).map(|_| values)
+}
 
-    match board.len() {
-        81 => Ok(board.into()),
-        _ => Err(io::ErrorKind::InvalidData.into()),
-    }
+fn solve(init: Board) -> Result<(Board, Report), String> {
+    let mut report = Report::default();
+    let board = search(init, &mut report)?;
+    Ok((board, report))
 }
 
-fn solve(init: Board) -> Result<Board, String> {
+fn search(init: Board, report: &mut Report) -> Result<Board, String> {
     let level = Level::new();
 
     debug!(target: "solve", "[{level}] trying elimination");
-    match eliminate(init) {
-        Ok((board, true)) => Ok(board),
-        Ok((board, false)) => {
-            debug!(target: "solve", "[{level}] trying depth first search");
-            match depth_first_search(board) {
-                Ok(board) => Ok(board),
-                Err(msg) => {
-                    debug!(target: "solve", "[{level}] depth first search failed: {msg}");
-                    Err(msg)
-                }
+    let mut board = match eliminate(init, report) {
+        Ok((board, true)) => return Ok(board),
+        Ok((board, false)) => board,
+        Err(msg) => {
+            debug!(target: "solve", "[{level}] elimination failed: {msg}");
+            return Err(msg);
+        }
+    };
+
+    loop {
+        debug!(target: "solve", "[{level}] trying candidate probing");
+        let changed = match probe(board, report) {
+            Ok((reduced, changed)) => {
+                board = reduced;
+                changed
             }
+            Err(msg) => {
+                debug!(target: "solve", "[{level}] probing failed: {msg}");
+                return Err(msg);
+            }
+        };
+        if !changed {
+            break;
         }
+
+        match eliminate(board, report) {
+            Ok((solved, true)) => return Ok(solved),
+            Ok((reduced, false)) => board = reduced,
+            Err(msg) => {
+                debug!(target: "solve", "[{level}] elimination failed: {msg}");
+                return Err(msg);
+            }
+        }
+    }
+
+    debug!(target: "solve", "[{level}] trying depth first search");
+    match depth_first_search(board, report) {
+        Ok(board) => Ok(board),
         Err(msg) => {
-            debug!(target: "solve", "[{level}] elimination failed: {msg}");
+            debug!(target: "solve", "[{level}] depth first search failed: {msg}");
             Err(msg)
         }
     }
 }
 
-fn eliminate(mut board: Board) -> Result<(Board, bool), String> {
+/// Forward-checking: tentatively assign each candidate of each unsolved cell and
+/// run [`eliminate`]; when the trial contradicts, that candidate is impossible
+/// and is dropped from the real board. Returns whether any candidate was removed
+/// so the caller can re-run elimination before the sweep is declared stalled.
+fn probe(mut board: Board, report: &mut Report) -> Result<(Board, bool), String> {
     let level = Level::new();
+    let cells = board.spec.cells;
+
+    let mut changed = false;
+    for i in 0..cells {
+        let candidates = match board[i] {
+            Cell::NotSolved(ref hints) => hints.iter().copied().collect::<Vec<_>>(),
+            Cell::Solved(_) => continue,
+        };
+
+        for value in candidates {
+            let mut trial = board.clone();
+            trial[i] = Cell::Solved(value);
+
+            if eliminate(trial, &mut Report::default()).is_err() {
+                debug!(target: "probe", "[{level}] cell({i}) cannot be {value}");
+                if let Cell::NotSolved(ref mut hints) = board[i] {
+                    hints.remove(&value);
+                    report.record(Technique::Probing, format!("cell({i}) cannot be {value}"));
+                    changed = true;
+                    if hints.is_empty() {
+                        return Err(format!("unsolvable cell at {i}"));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok((board, changed))
+}
+
+/// Picks the unsolved cell with the fewest remaining candidates (the
+/// minimum-remaining-values heuristic), shared by [`depth_first_search`] and
+/// [`search_all`]. Returns `None` once every cell is solved.
+fn min_candidate_cell(board: &Board) -> Option<(usize, BTreeSet<u8>)> {
+    let mut index = 0;
+    let mut hits: Option<&BTreeSet<u8>> = None;
+    for (i, c) in (0..).zip(board.iter()) {
+        if let Cell::NotSolved(h) = c
+            && (hits.is_none() || h.len() < hits.unwrap().len())
+        {
+            index = i;
+            hits = Some(h);
+        }
+    }
+
+    hits.map(|hints| (index, hints.clone()))
+}
+
+/// Enumerates up to `limit` distinct completed boards by driving the same MRV
+/// depth-first search as [`solve`], but continuing past the first success.
+/// Pass `limit == 2` to cheaply decide whether a puzzle is unique.
+fn solve_all(init: Board, limit: usize) -> Vec<Board> {
+    let mut solutions = Vec::new();
+    search_all(init, limit, &mut solutions);
+    solutions
+}
+
+fn search_all(init: Board, limit: usize, solutions: &mut Vec<Board>) {
+    if solutions.len() >= limit {
+        return;
+    }
+
+    let board = match eliminate(init, &mut Report::default()) {
+        Ok((board, true)) => {
+            solutions.push(board);
+            return;
+        }
+        Ok((board, false)) => board,
+        Err(_) => return,
+    };
+
+    let (index, hints) = match min_candidate_cell(&board) {
+        Some(found) => found,
+        None => {
+            solutions.push(board);
+            return;
+        }
+    };
+
+    for hint in hints {
+        if solutions.len() >= limit {
+            break;
+        }
+
+        let mut board = board.clone();
+        board[index] = Cell::Solved(hint);
+        search_all(board, limit, solutions);
+    }
+}
+
+fn eliminate(mut board: Board, report: &mut Report) -> Result<(Board, bool), String> {
+    let level = Level::new();
+    let BoardSpec {
+        box_size,
+        side,
+        cells,
+    } = board.spec;
 
     loop {
         let mut solved = true;
-        for i in 0..81 {
+        for i in 0..cells {
             if let Cell::Solved(answer) = board[i] {
-                let (row, col) = (i / 9, i % 9);
-                for i in (row * 9)..((row + 1) * 9) {
+                let (row, col) = (i / side, i % side);
+                for i in (row * side)..((row + 1) * side) {
                     if let Cell::NotSolved(ref mut hints) = board[i] {
                         hints.remove(&answer);
                     }
                 }
-                for i in (col..81).step_by(9) {
+                for i in (col..cells).step_by(side) {
                     if let Cell::NotSolved(ref mut hints) = board[i] {
                         hints.remove(&answer);
                     }
                 }
 
-                let (row, col) = (row / 3 * 3, col / 3 * 3);
-                for row in row..row + 3 {
-                    for col in col..col + 3 {
-                        if let Cell::NotSolved(ref mut hints) = board[row * 9 + col] {
+                let (row, col) = (row / box_size * box_size, col / box_size * box_size);
+                for row in row..row + box_size {
+                    for col in col..col + box_size {
+                        if let Cell::NotSolved(ref mut hints) = board[row * side + col] {
                             hints.remove(&answer);
                         }
                     }
@@ -164,7 +406,7 @@ fn eliminate(mut board: Board) -> Result<(Board, bool), String> {
         }
 
         let mut changed = false;
-        for i in 0..81 {
+        for i in 0..cells {
             if let Cell::NotSolved(ref hints) = board[i] {
                 if hints.is_empty() {
                     return Err(format!("unsolvable cell at {i}"));
@@ -176,31 +418,47 @@ fn eliminate(mut board: Board) -> Result<(Board, bool), String> {
 
                 let hint = *hints.iter().next().unwrap();
 
-                let (row, col) = (i / 9, i % 9);
-                for i in (row * 9)..((row + 1) * 9) {
+                let (row, col) = (i / side, i % side);
+                for i in (row * side)..((row + 1) * side) {
                     if matches!(board[i], Cell::Solved(n) if n == hint) {
                         return Err(format!("duplicate cell at {i}"));
                     }
                 }
-                for i in (col..81).step_by(9) {
+                for i in (col..cells).step_by(side) {
                     if matches!(board[i], Cell::Solved(n) if n == hint) {
                         return Err(format!("duplicate cell at {i}"));
                     }
                 }
 
-                let (row, col) = (row / 3 * 3, col / 3 * 3);
-                for row in row..row + 3 {
-                    for col in col..col + 3 {
-                        if matches!(board[row * 9 + col], Cell::Solved(n) if n == hint) {
+                let (row, col) = (row / box_size * box_size, col / box_size * box_size);
+                for row in row..row + box_size {
+                    for col in col..col + box_size {
+                        if matches!(board[row * side + col], Cell::Solved(n) if n == hint) {
                             return Err(format!("duplicate cell at ({col}, {row})"));
                         }
                     }
                 }
 
                 board[i] = Cell::Solved(hint);
+                report.record(Technique::NakedSingle, format!("cell({i}) = {hint}"));
                 changed = true;
             }
         }
+        if !changed {
+            if hidden_single(&mut board) {
+                report.record(Technique::HiddenSingle, "reduced a unit".to_owned());
+                changed = true;
+            } else if naked_subset(&mut board) {
+                report.record(Technique::NakedSubset, "reduced a unit".to_owned());
+                changed = true;
+            } else if pointing(&mut board) || box_line(&mut board) {
+                report.record(Technique::Pointing, "reduced a line".to_owned());
+                changed = true;
+            }
+            if changed {
+                debug!(target: "eliminate", "[{level}] propagation reduced candidates");
+            }
+        }
         if !changed {
             debug!(target: "eliminate", "[{level}] no cell could be solved");
             return Ok((board, false));
@@ -208,28 +466,248 @@ fn eliminate(mut board: Board) -> Result<(Board, bool), String> {
     }
 }
 
-fn depth_first_search(init: Board) -> Result<Board, String> {
-    let level = Level::new();
+/// Lists the index sets of every unit — each row, each column, each box.
+fn units(spec: &BoardSpec) -> Vec<Vec<usize>> {
+    let BoardSpec {
+        box_size,
+        side,
+        cells,
+    } = *spec;
 
-    let (index, hints) = {
-        let mut index = 0;
-        let mut hits: Option<&BTreeSet<u8>> = None;
-        for (i, c) in (0..).zip(init.iter()) {
-            if let Cell::NotSolved(h) = c {
-                if hits.is_none() || h.len() < hits.unwrap().len() {
-                    index = i;
-                    hits = Some(h);
+    let mut units = Vec::with_capacity(side * 3);
+    for r in 0..side {
+        units.push(((r * side)..((r + 1) * side)).collect());
+    }
+    for c in 0..side {
+        units.push((c..cells).step_by(side).collect());
+    }
+    for br in (0..side).step_by(box_size) {
+        for bc in (0..side).step_by(box_size) {
+            let mut unit = Vec::with_capacity(side);
+            for r in br..br + box_size {
+                for c in bc..bc + box_size {
+                    unit.push(r * side + c);
                 }
             }
+            units.push(unit);
+        }
+    }
+    units
+}
+
+/// Every `k`-sized combination of the given indices, in order.
+fn combinations(items: &[usize], k: usize) -> Vec<Vec<usize>> {
+    fn walk(items: &[usize], k: usize, start: usize, combo: &mut Vec<usize>, out: &mut Vec<Vec<usize>>) {
+        if combo.len() == k {
+            out.push(combo.clone());
+            return;
         }
+        for i in start..items.len() {
+            combo.push(items[i]);
+            walk(items, k, i + 1, combo, out);
+            combo.pop();
+        }
+    }
+
+    let mut out = Vec::new();
+    walk(items, k, 0, &mut Vec::with_capacity(k), &mut out);
+    out
+}
+
+/// Hidden single: if a value can go in only one cell of a unit, that cell must
+/// hold it, so reduce its hint set to that single value.
+fn hidden_single(board: &mut Board) -> bool {
+    let side = board.spec.side as u8;
+
+    let mut changed = false;
+    for unit in units(&board.spec) {
+        for value in 1..=side {
+            if unit
+                .iter()
+                .any(|&i| matches!(board[i], Cell::Solved(n) if n == value))
+            {
+                continue;
+            }
 
-        match hits {
-            Some(hints) => (index, hints.clone()),
-            None => {
-                debug!(target: "depth_first_search", "[{level}] already solved");
-                return Ok(init);
+            let mut holder = None;
+            let mut count = 0;
+            for &i in &unit {
+                if matches!(board[i], Cell::NotSolved(ref hints) if hints.contains(&value)) {
+                    holder = Some(i);
+                    count += 1;
+                }
+            }
+
+            if count == 1
+                && let Cell::NotSolved(ref mut hints) = board[holder.unwrap()]
+                && hints.len() > 1
+            {
+                hints.clear();
+                hints.insert(value);
+                changed = true;
             }
         }
+    }
+    changed
+}
+
+/// Naked pair/triple: if `k` cells in a unit share a hint set whose union is
+/// exactly `k` values, those values can be removed from the rest of the unit.
+fn naked_subset(board: &mut Board) -> bool {
+    let mut changed = false;
+    for unit in units(&board.spec) {
+        let unsolved = unit
+            .iter()
+            .copied()
+            .filter(|&i| matches!(board[i], Cell::NotSolved(_)))
+            .collect::<Vec<_>>();
+
+        for k in 2..=3 {
+            if unsolved.len() <= k {
+                continue;
+            }
+
+            for combo in combinations(&unsolved, k) {
+                let mut union = BTreeSet::new();
+                for &i in &combo {
+                    if let Cell::NotSolved(ref hints) = board[i] {
+                        union.extend(hints.iter().copied());
+                    }
+                }
+                if union.len() != k {
+                    continue;
+                }
+
+                for &i in &unit {
+                    if combo.contains(&i) {
+                        continue;
+                    }
+                    if let Cell::NotSolved(ref mut hints) = board[i]
+                        && union.iter().filter(|v| hints.remove(v)).count() > 0
+                    {
+                        changed = true;
+                    }
+                }
+            }
+        }
+    }
+    changed
+}
+
+/// Pointing pair: when every occurrence of a value within a box lies in a
+/// single row or column, remove it from the rest of that line outside the box.
+fn pointing(board: &mut Board) -> bool {
+    let BoardSpec {
+        box_size, side, ..
+    } = board.spec;
+
+    let mut changed = false;
+    for br in (0..side).step_by(box_size) {
+        for bc in (0..side).step_by(box_size) {
+            for value in 1..=side as u8 {
+                let mut rows = BTreeSet::new();
+                let mut cols = BTreeSet::new();
+                for r in br..br + box_size {
+                    for c in bc..bc + box_size {
+                        if matches!(board[r * side + c], Cell::NotSolved(ref h) if h.contains(&value)) {
+                            rows.insert(r);
+                            cols.insert(c);
+                        }
+                    }
+                }
+
+                if rows.len() == 1 {
+                    let r = *rows.iter().next().unwrap();
+                    for c in (0..side).filter(|&c| !(bc..bc + box_size).contains(&c)) {
+                        if let Cell::NotSolved(ref mut h) = board[r * side + c]
+                            && h.remove(&value)
+                        {
+                            changed = true;
+                        }
+                    }
+                }
+                if cols.len() == 1 {
+                    let c = *cols.iter().next().unwrap();
+                    for r in (0..side).filter(|&r| !(br..br + box_size).contains(&r)) {
+                        if let Cell::NotSolved(ref mut h) = board[r * side + c]
+                            && h.remove(&value)
+                        {
+                            changed = true;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    changed
+}
+
+/// Box-line reduction: when every occurrence of a value within a row or column
+/// lies in a single box, remove it from the rest of that box.
+fn box_line(board: &mut Board) -> bool {
+    let BoardSpec {
+        box_size, side, ..
+    } = board.spec;
+
+    let mut changed = false;
+    for value in 1..=side as u8 {
+        for r in 0..side {
+            let mut boxes = BTreeSet::new();
+            for c in 0..side {
+                if matches!(board[r * side + c], Cell::NotSolved(ref h) if h.contains(&value)) {
+                    boxes.insert(c / box_size);
+                }
+            }
+            if boxes.len() == 1 {
+                let bc = *boxes.iter().next().unwrap() * box_size;
+                let br = r / box_size * box_size;
+                for rr in (br..br + box_size).filter(|&rr| rr != r) {
+                    for cc in bc..bc + box_size {
+                        if let Cell::NotSolved(ref mut h) = board[rr * side + cc]
+                            && h.remove(&value)
+                        {
+                            changed = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        for c in 0..side {
+            let mut boxes = BTreeSet::new();
+            for r in 0..side {
+                if matches!(board[r * side + c], Cell::NotSolved(ref h) if h.contains(&value)) {
+                    boxes.insert(r / box_size);
+                }
+            }
+            if boxes.len() == 1 {
+                let br = *boxes.iter().next().unwrap() * box_size;
+                let bc = c / box_size * box_size;
+                for rr in br..br + box_size {
+                    for cc in (bc..bc + box_size).filter(|&cc| cc != c) {
+                        if let Cell::NotSolved(ref mut h) = board[rr * side + cc]
+                            && h.remove(&value)
+                        {
+                            changed = true;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    changed
+}
+
+fn depth_first_search(init: Board, report: &mut Report) -> Result<Board, String> {
+    let level = Level::new();
+    report.record(Technique::Search, format!("branching at depth {level}"));
+
+    let (index, hints) = match min_candidate_cell(&init) {
+        Some(found) => found,
+        None => {
+            debug!(target: "depth_first_search", "[{level}] already solved");
+            return Ok(init);
+        }
     };
 
     for hint in hints {
@@ -238,15 +716,123 @@ fn depth_first_search(init: Board) -> Result<Board, String> {
         debug!(target: "depth_first_search", "[{level}] assuming cell({index}) = {hint}");
         board[index] = Cell::Solved(hint);
 
-        match solve(board) {
+        let checkpoint = report.checkpoint();
+        match search(board, report) {
             Ok(board) => return Ok(board),
-            Err(msg) => debug!(target: "depth_first_search", "[{level}] cloud not solve: {msg}"),
+            Err(msg) => {
+                report.rollback(checkpoint);
+                debug!(target: "depth_first_search", "[{level}] cloud not solve: {msg}");
+            }
         }
     }
 
     Err("all assumptions contradicted".to_owned())
 }
 
+/// A solving technique, ordered from cheapest to most expensive. The ordinal
+/// order is the cost order, so the most expensive technique that made progress
+/// is simply the maximum key in a [`Report`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Technique {
+    NakedSingle,
+    HiddenSingle,
+    NakedSubset,
+    Pointing,
+    Probing,
+    Search,
+}
+
+impl fmt::Display for Technique {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::NakedSingle => "naked single",
+            Self::HiddenSingle => "hidden single",
+            Self::NakedSubset => "naked pair/triple",
+            Self::Pointing => "pointing/box-line",
+            Self::Probing => "probing",
+            Self::Search => "depth first search",
+        })
+    }
+}
+
+/// A difficulty rating, derived from the hardest technique a solve required.
+#[derive(Debug, Clone, Copy)]
+enum Grade {
+    Easy,
+    Medium,
+    Hard,
+    Evil,
+}
+
+impl fmt::Display for Grade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Easy => "Easy",
+            Self::Medium => "Medium",
+            Self::Hard => "Hard",
+            Self::Evil => "Evil",
+        })
+    }
+}
+
+/// Records which techniques a solve used: an ordered log of deductions and a
+/// tally of how many times each technique fired.
+#[derive(Debug, Default)]
+struct Report {
+    counts: BTreeMap<Technique, usize>,
+    log: Vec<String>,
+}
+
+impl Report {
+    fn record(&mut self, technique: Technique, detail: String) {
+        *self.counts.entry(technique).or_default() += 1;
+        self.log.push(format!("{technique}: {detail}"));
+    }
+
+    /// Captures the current log/tally so a contradicted branch can be undone
+    /// with [`Report::rollback`] instead of polluting the report with
+    /// deductions from a path that turned out to be wrong.
+    fn checkpoint(&self) -> (usize, BTreeMap<Technique, usize>) {
+        (self.log.len(), self.counts.clone())
+    }
+
+    /// Discards everything recorded since `checkpoint`, restoring the tally
+    /// to what it was at that point.
+    fn rollback(&mut self, checkpoint: (usize, BTreeMap<Technique, usize>)) {
+        let (log_len, counts) = checkpoint;
+        self.log.truncate(log_len);
+        self.counts = counts;
+    }
+
+    /// Maps the hardest technique that made progress, and a weighted tally of
+    /// every technique that fired, to a difficulty grade. The hardest
+    /// technique sets a floor grade; a heavy weighted tally (one more than a
+    /// technique's ordinal, times how often it fired, summed over the whole solve)
+    /// bumps the grade up one level even when the hardest technique alone
+    /// would not.
+    fn grade(&self) -> Grade {
+        let floor = match self.counts.keys().max() {
+            None | Some(Technique::NakedSingle) => Grade::Easy,
+            Some(Technique::HiddenSingle | Technique::NakedSubset) => Grade::Medium,
+            Some(Technique::Pointing | Technique::Probing) => Grade::Hard,
+            Some(Technique::Search) => Grade::Evil,
+        };
+
+        let weighted: usize = self
+            .counts
+            .iter()
+            .map(|(technique, count)| (*technique as usize + 1) * count)
+            .sum();
+
+        match floor {
+            Grade::Easy if weighted > 8 => Grade::Medium,
+            Grade::Medium if weighted > 20 => Grade::Hard,
+            Grade::Hard if weighted > 40 => Grade::Evil,
+            floor => floor,
+        }
+    }
+}
+
 static LEVEL: AtomicUsize = AtomicUsize::new(0);
 
 #[derive(Debug)]
@@ -269,3 +855,173 @@ impl fmt::Display for Level {
         write!(f, "{}", self.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC_4X4: BoardSpec = BoardSpec {
+        box_size: 2,
+        side: 4,
+        cells: 16,
+    };
+
+    #[test]
+    fn hidden_single_narrows_the_only_holder_of_a_value() {
+        // Row 0 is the only unit under test: cell(0) can hold 2 or 3, cell(1)
+        // can hold 3 or 4, and every other cell is already solved. Only
+        // cell(0) can take 2, and (once that's settled) only cell(1) can
+        // take 3, even though neither cell started as a naked single.
+        let mut cells = vec![Cell::Solved(1); 16];
+        cells[0] = Cell::NotSolved(BTreeSet::from([2, 3]));
+        cells[1] = Cell::NotSolved(BTreeSet::from([3, 4]));
+        let mut board = Board {
+            spec: SPEC_4X4,
+            cells,
+        };
+
+        assert!(hidden_single(&mut board));
+        assert_eq!(board[0], Cell::NotSolved(BTreeSet::from([2])));
+        assert_eq!(board[1], Cell::NotSolved(BTreeSet::from([3])));
+    }
+
+    #[test]
+    fn naked_subset_strips_a_pair_from_the_rest_of_the_unit() {
+        // Row 1: cell(4) and cell(5) share the hint set {1, 2} (a naked
+        // pair), so those values can be dropped from cell(6)'s hints even
+        // though cell(6) never held 1 or 2 exclusively.
+        let mut cells = vec![Cell::Solved(4); 16];
+        cells[4] = Cell::NotSolved(BTreeSet::from([1, 2]));
+        cells[5] = Cell::NotSolved(BTreeSet::from([1, 2]));
+        cells[6] = Cell::NotSolved(BTreeSet::from([1, 2, 3]));
+        let mut board = Board {
+            spec: SPEC_4X4,
+            cells,
+        };
+
+        assert!(naked_subset(&mut board));
+        assert_eq!(board[4], Cell::NotSolved(BTreeSet::from([1, 2])));
+        assert_eq!(board[5], Cell::NotSolved(BTreeSet::from([1, 2])));
+        assert_eq!(board[6], Cell::NotSolved(BTreeSet::from([3])));
+    }
+
+    #[test]
+    fn pointing_clears_a_value_confined_to_one_row_of_a_box() {
+        // Inside the top-left box, every remaining candidate for 3 falls in
+        // row 0 (cell(0) and cell(1)), so 3 can be dropped from the rest of
+        // row 0 outside that box (cell(2) and cell(3)).
+        let mut cells = vec![Cell::Solved(1); 16];
+        cells[0] = Cell::NotSolved(BTreeSet::from([3]));
+        cells[1] = Cell::NotSolved(BTreeSet::from([3]));
+        cells[2] = Cell::NotSolved(BTreeSet::from([3, 4]));
+        cells[3] = Cell::NotSolved(BTreeSet::from([2, 3]));
+        let mut board = Board {
+            spec: SPEC_4X4,
+            cells,
+        };
+
+        assert!(pointing(&mut board));
+        assert_eq!(board[0], Cell::NotSolved(BTreeSet::from([3])));
+        assert_eq!(board[1], Cell::NotSolved(BTreeSet::from([3])));
+        assert_eq!(board[2], Cell::NotSolved(BTreeSet::from([4])));
+        assert_eq!(board[3], Cell::NotSolved(BTreeSet::from([2])));
+    }
+
+    /// Builds a board straight from puzzle text, the same way [`open`] does
+    /// minus the file read, so tests can express puzzles as plain strings.
+    fn board_from(content: &str) -> Board {
+        let values = parse_values(content).expect("valid puzzle text");
+        let spec = BoardSpec::from_cells(values.len()).expect("square board");
+        let side = spec.side as u8;
+        let cells = values
+            .into_iter()
+            .map(|v| match v {
+                0 => Cell::NotSolved((1..=side).collect()),
+                n => Cell::Solved(n),
+            })
+            .collect();
+
+        Board { spec, cells }
+    }
+
+    #[test]
+    fn solve_all_counts_an_ambiguous_puzzle_as_two() {
+        // The four blanks form a minimal deadly pattern: rows 0 and 1 each
+        // need {1, 3} at columns 0 and 2, and swapping which column gets
+        // which value yields a second, equally valid completion.
+        let board = board_from(
+            "0 2 0 4
+             0 4 0 2
+             2 1 4 3
+             4 3 2 1",
+        );
+
+        assert_eq!(solve_all(board, 2).len(), 2);
+    }
+
+    #[test]
+    fn probe_drops_a_candidate_that_forces_a_peer_empty() {
+        // cell(0) can hold 1 or 2; cell(1), its row peer, can only hold 1.
+        // Trying cell(0) = 1 would strip cell(1)'s only candidate, so probing
+        // must discard 1 from cell(0) while leaving cell(1)'s {1} untouched.
+        let cells = vec![
+            Cell::NotSolved(BTreeSet::from([1, 2])),
+            Cell::NotSolved(BTreeSet::from([1])),
+            Cell::Solved(3),
+            Cell::Solved(4),
+            Cell::Solved(3),
+            Cell::Solved(4),
+            Cell::Solved(1),
+            Cell::Solved(2),
+            Cell::Solved(3),
+            Cell::Solved(2),
+            Cell::Solved(1),
+            Cell::Solved(1),
+            Cell::Solved(4),
+            Cell::Solved(3),
+            Cell::Solved(1),
+            Cell::Solved(1),
+        ];
+        let board = Board {
+            spec: SPEC_4X4,
+            cells,
+        };
+
+        let (board, changed) = probe(board, &mut Report::default()).expect("no contradiction");
+
+        assert!(changed);
+        assert_eq!(board[0], Cell::NotSolved(BTreeSet::from([2])));
+        assert_eq!(board[1], Cell::NotSolved(BTreeSet::from([1])));
+    }
+
+    fn graded(counts: &[(Technique, usize)]) -> Grade {
+        let mut report = Report::default();
+        report.counts = counts.iter().copied().collect();
+        report.grade()
+    }
+
+    #[test]
+    fn grade_floors_on_the_hardest_technique_used() {
+        assert!(matches!(graded(&[]), Grade::Easy));
+        assert!(matches!(
+            graded(&[(Technique::NakedSingle, 3)]),
+            Grade::Easy
+        ));
+        assert!(matches!(
+            graded(&[(Technique::NakedSingle, 5), (Technique::HiddenSingle, 2)]),
+            Grade::Medium
+        ));
+        assert!(matches!(graded(&[(Technique::Search, 1)]), Grade::Evil));
+    }
+
+    #[test]
+    fn grade_bumps_up_a_level_on_a_heavy_weighted_tally() {
+        // Naked singles alone floor at Easy, but enough of them push the
+        // weighted tally past the Easy→Medium threshold even though no
+        // harder technique ever fired.
+        assert!(matches!(
+            graded(&[(Technique::NakedSingle, 10)]),
+            Grade::Medium
+        ));
+    }
+}